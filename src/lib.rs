@@ -0,0 +1,439 @@
+use std::fmt;
+
+/// Conversion constants for decimal (1000-based) units.
+pub const KB_DECIMAL: f64 = 1000.0;
+pub const MB_DECIMAL: f64 = 1_000_000.0;
+pub const GB_DECIMAL: f64 = 1_000_000_000.0;
+
+/// Conversion constants for binary (1024-based) units.
+pub const KB_BINARY: f64 = 1024.0;
+pub const MB_BINARY: f64 = 1_048_576.0;
+pub const GB_BINARY: f64 = 1_073_741_824.0;
+
+/// The unit system used when converting and displaying sizes.
+///
+/// `Decimal` uses 1000-based divisors with `kb`/`mb`/`gb` labels,
+/// `Binary` uses 1024-based divisors with `kib`/`mib`/`gib` labels, and
+/// `Conventional` uses 1024-based divisors but keeps the `kb`/`mb`/`gb`
+/// labels (matching how most OS file managers report sizes).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Base {
+    Decimal,
+    Binary,
+    Conventional,
+}
+
+impl Base {
+    // Parse a `--base` flag value into a Base.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "decimal" => Ok(Base::Decimal),
+            "binary" => Ok(Base::Binary),
+            "conventional" => Ok(Base::Conventional),
+            _ => Err(format!(
+                "Invalid base: '{}'. Supported bases: 'decimal', 'binary', or 'conventional'.",
+                value
+            )),
+        }
+    }
+
+    // The KB/MB/GB divisors to use for this base.
+    pub fn factors(&self) -> (f64, f64, f64) {
+        match self {
+            Base::Decimal => (KB_DECIMAL, MB_DECIMAL, GB_DECIMAL),
+            Base::Binary | Base::Conventional => (KB_BINARY, MB_BINARY, GB_BINARY),
+        }
+    }
+
+    // The unit labels to display for this base.
+    pub fn labels(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Base::Decimal | Base::Conventional => ("kb", "mb", "gb"),
+            Base::Binary => ("kib", "mib", "gib"),
+        }
+    }
+
+    // The full scale table for this base, ordered from largest to smallest
+    // unit. Public so callers can build custom formatting (their own
+    // decimal places, long unit names, separators, ...) on top of it.
+    pub fn scale_table(&self) -> [(f64, &'static str); 7] {
+        match self {
+            Base::Decimal => [
+                (1_000_000_000_000_000_000.0, "eb"),
+                (1_000_000_000_000_000.0, "pb"),
+                (1_000_000_000_000.0, "tb"),
+                (1_000_000_000.0, "gb"),
+                (1_000_000.0, "mb"),
+                (1_000.0, "kb"),
+                (1.0, "bytes"),
+            ],
+            Base::Binary => [
+                (1_152_921_504_606_846_976.0, "eib"),
+                (1_125_899_906_842_624.0, "pib"),
+                (1_099_511_627_776.0, "tib"),
+                (1_073_741_824.0, "gib"),
+                (1_048_576.0, "mib"),
+                (1_024.0, "kib"),
+                (1.0, "bytes"),
+            ],
+            Base::Conventional => [
+                (1_152_921_504_606_846_976.0, "eb"),
+                (1_125_899_906_842_624.0, "pb"),
+                (1_099_511_627_776.0, "tb"),
+                (1_073_741_824.0, "gb"),
+                (1_048_576.0, "mb"),
+                (1_024.0, "kb"),
+                (1.0, "bytes"),
+            ],
+        }
+    }
+
+    // Format a byte count as a single string, picking the largest unit for
+    // which the value is >= 1. A zero remainder prints without a trailing `.00`.
+    pub fn humanize(&self, size_in_bytes: u64, precision: usize) -> String {
+        let bytes = size_in_bytes as f64;
+        for (factor, label) in self.scale_table() {
+            if bytes >= factor {
+                let value = bytes / factor;
+                return format_value(value, label, precision);
+            }
+        }
+        format_value(bytes, "bytes", precision)
+    }
+}
+
+// Format a scaled value with the given precision, dropping a trailing
+// `.00`-style remainder so whole numbers print without decimals.
+fn format_value(value: f64, label: &str, precision: usize) -> String {
+    if value.fract() == 0.0 {
+        format!("{} {}", value as u64, label)
+    } else {
+        format!("{:.*} {}", precision, value, label)
+    }
+}
+
+/// A struct to hold a single file size represented in different units.
+pub struct Sizes {
+    bytes: String,
+    kilobytes: String,
+    megabytes: String,
+    gigabytes: String,
+}
+
+/// Implement methods for the Sizes struct to convert from bytes to other units.
+impl Sizes {
+    // Create a new Sizes instance from a given size in bytes and unit base.
+    pub fn from_bytes(size_in_bytes: u64, base: Base) -> Self {
+        let (kb, mb, gb) = base.factors();
+        let (kb_label, mb_label, gb_label) = base.labels();
+        Self {
+            bytes: format!("{} bytes", size_in_bytes),
+            kilobytes: format!("{:.2} {}", size_in_bytes as f64 / kb, kb_label),
+            megabytes: format!("{:.2} {}", size_in_bytes as f64 / mb, mb_label),
+            gigabytes: format!("{:.2} {}", size_in_bytes as f64 / gb, gb_label),
+        }
+    }
+}
+
+/// Implement the Display trait for Sizes to format the output.
+impl fmt::Display for Sizes {
+    // Format the Sizes struct for display.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "   bytes: {}", self.bytes)?;
+        writeln!(f, "   kilobytes: {}", self.kilobytes)?;
+        writeln!(f, "   megabytes: {}", self.megabytes)?;
+        writeln!(f, "   gigabytes: {}", self.gigabytes)?;
+        Ok(())
+    }
+}
+
+/// Enum to represent file sizes in different units.
+#[derive(Debug)]
+pub enum FileSize {
+    Bytes(u64),
+    Kilobytes(f64),
+    Megabytes(f64),
+    Gigabytes(f64),
+    Kibibytes(f64),
+    Mebibytes(f64),
+    Gibibytes(f64),
+}
+
+/// Implement methods for the FileSize enum to create instances and normalize sizes.
+impl FileSize {
+    // Create a new FileSize instance based on the size and unit provided.
+    pub fn new(size: f64, unit: &str) -> Result<Self, String> {
+        if size < 0.0 {
+            return Err("Invalid file size. Size cannot be a negative number.".to_string()); // Return an error if the file size is negative
+        }
+
+        match unit {
+            "bytes" => Ok(FileSize::Bytes(size as u64)),
+            "kb" => Ok(FileSize::Kilobytes(size)),
+            "mb" => Ok(FileSize::Megabytes(size)),
+            "gb" => Ok(FileSize::Gigabytes(size)),
+            "kib" => Ok(FileSize::Kibibytes(size)),
+            "mib" => Ok(FileSize::Mebibytes(size)),
+            "gib" => Ok(FileSize::Gibibytes(size)),
+            _ => Err(
+                "Error: Invalid unit. Supported units: 'bytes', 'kb', 'mb', 'gb', 'kib', 'mib', or 'gib'."
+                    .to_string(),
+            ), // Return an error if the unit is invalid
+        }
+    }
+
+    // Normalize the file size to bytes.
+    pub fn normalize_to_bytes(&self) -> u64 {
+        match self {
+            FileSize::Bytes(size) => *size,
+            FileSize::Kilobytes(size) => (*size * KB_DECIMAL) as u64,
+            FileSize::Megabytes(size) => (*size * MB_DECIMAL) as u64,
+            FileSize::Gigabytes(size) => (*size * GB_DECIMAL) as u64,
+            FileSize::Kibibytes(size) => (*size * KB_BINARY) as u64,
+            FileSize::Mebibytes(size) => (*size * MB_BINARY) as u64,
+            FileSize::Gibibytes(size) => (*size * GB_BINARY) as u64,
+        }
+    }
+
+    // Parse a single combined token such as "1.5GiB", "1024KiB", or a bare
+    // number ("900", taken as bytes), splitting the numeric prefix from the
+    // trailing unit suffix and matching the suffix case-insensitively.
+    pub fn parse(token: &str) -> Result<Self, String> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err("Invalid file size. Size cannot be empty.".to_string());
+        }
+
+        let split_idx = token
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(token.len());
+        let (num_part, unit_part) = token.split_at(split_idx);
+
+        let size: f64 = num_part
+            .parse()
+            .map_err(|error| format!("Invalid file size: {}. Size cannot be a non-numeric value.", error))?;
+
+        let unit_part = unit_part.trim();
+        let unit = if unit_part.is_empty() {
+            "bytes".to_string()
+        } else {
+            unit_part.to_lowercase()
+        };
+
+        FileSize::new(size, &unit)
+    }
+}
+
+/// The adjustment an operator-prefixed size argument applies to a reference
+/// size: `Add`/`Subtract` extend or reduce it (subtraction clamped to a zero
+/// minimum), `RoundUpTo`/`RoundDownTo` round it up or down, respectively, to
+/// a multiple of the magnitude (a zero magnitude leaves it unchanged), and
+/// `Set` replaces it outright.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operator {
+    Set,
+    Add,
+    Subtract,
+    RoundUpTo,
+    RoundDownTo,
+}
+
+/// A size argument paired with the operator it should apply to a reference size.
+pub struct SizeAdjustment {
+    pub operator: Operator,
+    pub magnitude: FileSize,
+}
+
+impl SizeAdjustment {
+    // Split a leading `+`, `-`, `%`, or `/` operator off of a size token.
+    pub fn strip_operator(token: &str) -> (Operator, &str) {
+        let token = token.trim();
+        match token.chars().next() {
+            Some('+') => (Operator::Add, &token[1..]),
+            Some('-') => (Operator::Subtract, &token[1..]),
+            Some('%') => (Operator::RoundUpTo, &token[1..]),
+            Some('/') => (Operator::RoundDownTo, &token[1..]),
+            _ => (Operator::Set, token),
+        }
+    }
+
+    // Parse a size token that may carry a leading `+`, `-`, `%`, or `/` operator.
+    pub fn parse(token: &str) -> Result<Self, String> {
+        let (operator, rest) = SizeAdjustment::strip_operator(token);
+        let magnitude = FileSize::parse(rest)?;
+        Ok(SizeAdjustment { operator, magnitude })
+    }
+
+    // Apply the adjustment to a reference size (in bytes), returning the
+    // resulting size in bytes.
+    pub fn apply(&self, base: u64) -> u64 {
+        let magnitude = self.magnitude.normalize_to_bytes();
+        match self.operator {
+            Operator::Set => magnitude,
+            Operator::Add => base.saturating_add(magnitude),
+            Operator::Subtract => base.saturating_sub(magnitude),
+            Operator::RoundUpTo if magnitude == 0 => base,
+            Operator::RoundUpTo => {
+                let remainder = base % magnitude;
+                if remainder == 0 {
+                    base
+                } else {
+                    base.saturating_add(magnitude - remainder)
+                }
+            }
+            Operator::RoundDownTo if magnitude == 0 => base,
+            Operator::RoundDownTo => base - (base % magnitude),
+        }
+    }
+}
+
+/// The comparator a `--filter` threshold applies: `GreaterThan` keeps sizes
+/// above the threshold, `LessThan` keeps sizes below it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Comparator {
+    GreaterThan,
+    LessThan,
+}
+
+/// A size threshold paired with the comparator a `--filter` mode checks
+/// candidate sizes against.
+pub struct SizeFilter {
+    pub comparator: Comparator,
+    pub threshold: FileSize,
+}
+
+impl SizeFilter {
+    // Parse a `--filter` token such as "+1gb" or "-500mb".
+    pub fn parse(token: &str) -> Result<Self, String> {
+        let token = token.trim();
+        let (comparator, rest) = match token.chars().next() {
+            Some('+') => (Comparator::GreaterThan, &token[1..]),
+            Some('-') => (Comparator::LessThan, &token[1..]),
+            _ => {
+                return Err(
+                    "Invalid filter. Must start with '+' (greater than) or '-' (less than).".to_string(),
+                )
+            }
+        };
+
+        let threshold = FileSize::parse(rest)?;
+        Ok(SizeFilter { comparator, threshold })
+    }
+
+    // Check whether a size in bytes satisfies the filter's threshold.
+    pub fn matches(&self, size_in_bytes: u64) -> bool {
+        let threshold_bytes = self.threshold.normalize_to_bytes();
+        match self.comparator {
+            Comparator::GreaterThan => size_in_bytes > threshold_bytes,
+            Comparator::LessThan => size_in_bytes < threshold_bytes,
+        }
+    }
+}
+
+/// Format a raw numeric size as a human-friendly size string.
+pub trait FormatSize {
+    fn format_size(self, base: Base, precision: usize) -> String;
+}
+
+impl FormatSize for u64 {
+    fn format_size(self, base: Base, precision: usize) -> String {
+        base.humanize(self, precision)
+    }
+}
+
+impl FormatSize for f64 {
+    fn format_size(self, base: Base, precision: usize) -> String {
+        base.humanize(self.max(0.0) as u64, precision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_rejects_negative_size() {
+        let error = FileSize::new(-1.0, "kb").unwrap_err();
+        assert!(error.contains("cannot be a negative number"));
+    }
+
+    #[test]
+    fn parse_bare_number_is_bytes() {
+        let size = FileSize::parse("900").unwrap();
+        assert_eq!(size.normalize_to_bytes(), 900);
+    }
+
+    #[test]
+    fn parse_is_case_insensitive_and_trims_whitespace() {
+        let size = FileSize::parse(" 1.5GiB ").unwrap();
+        assert_eq!(size.normalize_to_bytes(), 1_610_612_736);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_unit() {
+        assert!(FileSize::parse("5tb_oops").is_err());
+    }
+
+    #[test]
+    fn normalize_to_bytes_uses_decimal_and_binary_factors() {
+        assert_eq!(FileSize::new(1.0, "kb").unwrap().normalize_to_bytes(), 1000);
+        assert_eq!(FileSize::new(1.0, "kib").unwrap().normalize_to_bytes(), 1024);
+    }
+
+    #[test]
+    fn size_adjustment_set_ignores_reference() {
+        let adjustment = SizeAdjustment::parse("500mb").unwrap();
+        assert_eq!(adjustment.apply(1_000_000_000), 500_000_000);
+    }
+
+    #[test]
+    fn size_adjustment_add_extends_reference() {
+        let adjustment = SizeAdjustment::parse("+500mb").unwrap();
+        assert_eq!(adjustment.apply(1_000_000_000), 1_500_000_000);
+    }
+
+    #[test]
+    fn size_adjustment_subtract_clamps_to_zero() {
+        let adjustment = SizeAdjustment::parse("-5gb").unwrap();
+        assert_eq!(adjustment.apply(100_000_000), 0);
+    }
+
+    #[test]
+    fn size_adjustment_rounds_up_and_down_to_multiple() {
+        let round_up = SizeAdjustment::parse("%4096").unwrap();
+        assert_eq!(round_up.apply(5000), 8192);
+
+        let round_down = SizeAdjustment::parse("/4096").unwrap();
+        assert_eq!(round_down.apply(5000), 4096);
+    }
+
+    #[test]
+    fn size_adjustment_round_up_saturates_instead_of_overflowing() {
+        let round_up = SizeAdjustment::parse("%4").unwrap();
+        assert_eq!(round_up.apply(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn size_filter_matches_greater_and_less_than() {
+        let greater_than = SizeFilter::parse("+1gb").unwrap();
+        assert!(greater_than.matches(2_000_000_000));
+        assert!(!greater_than.matches(500_000_000));
+
+        let less_than = SizeFilter::parse("-1gb").unwrap();
+        assert!(less_than.matches(500_000_000));
+        assert!(!less_than.matches(2_000_000_000));
+    }
+
+    #[test]
+    fn humanize_picks_largest_fitting_unit_and_drops_trailing_zero_decimals() {
+        assert_eq!(Base::Decimal.humanize(900, 2), "900 bytes");
+        assert_eq!(Base::Decimal.humanize(4_200_000_000, 2), "4.20 gb");
+        assert_eq!(Base::Binary.humanize(1_073_741_824, 2), "1 gib");
+    }
+
+    #[test]
+    fn humanize_conventional_uses_binary_factors_with_decimal_labels() {
+        assert_eq!(Base::Conventional.humanize(1_073_741_824, 2), "1 gb");
+        assert_eq!(Base::Conventional.humanize(1_610_612_736, 2), "1.50 gb");
+    }
+}
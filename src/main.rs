@@ -1,134 +1,220 @@
 use std::env;
-use std::fmt;
-
-/// Constants for file size conversions.
-const KB: f64 = 1000.0;
-const MB: f64 = 1_000_000.0;
-const GB: f64 = 1_000_000_000.0;
-
-/// A struct to hold a single file size represented in different units.
-struct Sizes {
-    bytes: String,
-    kilobytes: String,
-    megabytes: String,
-    gigabytes: String,
-}
+use std::io::{self, BufRead};
+
+use rust_filesize_formatter::{Base, FileSize, Operator, SizeAdjustment, SizeFilter, Sizes};
+
+fn main() {
+    let args: Vec<String> = env::args().collect(); // Collect command line arguments
 
-/// Implement methods for the Sizes struct to convert from bytes to other units.
-impl Sizes {
-    // Create a new Sizes instance from a given size in bytes.
-    fn from_bytes(size_in_bytes: u64) -> Self {
-        Self {
-            bytes: format!("{} bytes", size_in_bytes),
-            kilobytes: format!("{:.2} kb", size_in_bytes as f64 / KB),
-            megabytes: format!("{:.2} mb", size_in_bytes as f64 / MB),
-            gigabytes: format!("{:.2} gb", size_in_bytes as f64 / GB),
+    // Separate out the `--base`/`--humanize`/`--reference`/`--filter` flags from the positional args.
+    let mut positional: Vec<String> = Vec::new();
+    let mut base = Base::Decimal;
+    let mut humanize = false;
+    let mut reference_bytes: Option<u64> = None;
+    let mut filter: Option<String> = None;
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == "--filter" {
+            match iter.next() {
+                Some(value) => filter = Some(value.clone()),
+                None => {
+                    eprintln!("Error: --filter requires a threshold value (e.g. '+1gb' or '-500mb').");
+                    std::process::exit(2);
+                }
+            }
+        } else if arg == "--base" {
+            match iter.next() {
+                Some(value) => match Base::parse(value) {
+                    Ok(parsed) => base = parsed,
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        std::process::exit(2); // Exit with code 2 for invalid base input
+                    }
+                },
+                None => {
+                    eprintln!("Error: --base requires a value ('decimal', 'binary', or 'conventional').");
+                    std::process::exit(2);
+                }
+            }
+        } else if arg == "--humanize" {
+            humanize = true;
+        } else if arg == "--reference" {
+            match iter.next() {
+                Some(value) => match FileSize::parse(value) {
+                    Ok(size) => reference_bytes = Some(size.normalize_to_bytes()),
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        std::process::exit(2); // Exit with code 2 for invalid reference size
+                    }
+                },
+                None => {
+                    eprintln!("Error: --reference requires a size value.");
+                    std::process::exit(2);
+                }
+            }
+        } else {
+            positional.push(arg.clone());
         }
     }
-}
 
-/// Implement the Display trait for Sizes to format the output.
-impl fmt::Display for Sizes {
-    // Format the Sizes struct for display.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "   bytes: {}", self.bytes)?;
-        writeln!(f, "   kilobytes: {}", self.kilobytes)?;
-        writeln!(f, "   megabytes: {}", self.megabytes)?;
-        writeln!(f, "   gigabytes: {}", self.gigabytes)?;
-        Ok(())
-    }
-}
+    // `--filter` runs a distinct mode: read sizes from stdin and echo only
+    // those that satisfy the threshold, exiting 1 when nothing matched.
+    if let Some(filter_token) = filter {
+        if !positional.is_empty() {
+            eprintln!("Error: --filter does not take positional size arguments; sizes are read from stdin.");
+            std::process::exit(2);
+        }
 
-/// Enum to represent file sizes in different units.
-enum FileSize {
-    Bytes(u64),
-    Kilobytes(f64),
-    Megabytes(f64),
-    Gigabytes(f64),
-}
+        let size_filter = match SizeFilter::parse(&filter_token) {
+            Ok(size_filter) => size_filter,
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(2);
+            }
+        };
 
-/// Implement methods for the FileSize enum to create instances and normalize sizes.
-impl FileSize {
-    // Create a new FileSize instance based on the size and unit provided.
-    fn new(size: f64, unit: &str) -> Result<Self, String> {
-        if size < 0.0 {
-            return Err("Invalid file size. Size cannot be a negative number.".to_string()); // Return an error if the file size is negative
+        let stdin = io::stdin();
+        let mut matched = false;
+        for line in stdin.lock().lines() {
+            let line = line.unwrap_or_else(|error| {
+                eprintln!("Error reading from stdin: {}", error);
+                std::process::exit(1);
+            });
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match FileSize::parse(line) {
+                Ok(size) => {
+                    if size_filter.matches(size.normalize_to_bytes()) {
+                        println!("{}", line);
+                        matched = true;
+                    }
+                }
+                Err(error) => eprintln!("{}", error),
+            }
         }
 
-        match unit {
-            "bytes" => Ok(FileSize::Bytes(size as u64)),
-            "kb" => Ok(FileSize::Kilobytes(size)),
-            "mb" => Ok(FileSize::Megabytes(size)),
-            "gb" => Ok(FileSize::Gigabytes(size)),
-            _ => Err("Error: Invalid unit. Supported units: 'bytes', 'kb', 'mb', or 'gb'.".to_string()), // Return an error if the unit is invalid
-        }
+        std::process::exit(if matched { 0 } else { 1 });
     }
 
-    // Normalize the file size to bytes.
-    fn normalize_to_bytes(&self) -> u64 {
-        match self {
-            FileSize::Bytes(size) => *size,
-            FileSize::Kilobytes(size) => (*size * KB) as u64,
-            FileSize::Megabytes(size) => (*size * MB) as u64,
-            FileSize::Gigabytes(size) => (*size * GB) as u64,
-        }
-    }
-}
+    match positional.len() {
+        // Legacy two-argument form: `<file_size> <unit>`, with an optional leading operator on the size.
+        2 => {
+            let input = format!("{} {}", positional[0], positional[1]); // Combine the file size and unit input for display
 
-fn main() {
-    let args: Vec<String> = env::args().collect(); // Collect command line arguments
+            let (operator, size_token) = SizeAdjustment::strip_operator(&positional[0]);
 
-    // Check if the correct number of arguments is provided
-    if args.len() != 3 {
-        eprintln!("Usage: {} <file_size> <unit (bytes/kb/mb/gb)>", args[0]);
-        std::process::exit(2); // Exit with code 2 for incorrect command usage
-    }
+            // Validate and parse the file size argument
+            let file_size: f64 = match size_token.parse::<f64>() {
+                Ok(size_input) if size_input >= 0.0 => size_input,
+                Ok(_) => {
+                    eprintln!("Invalid file size. Size cannot be a negative number.");
+                    std::process::exit(4); // Exit with code 4 for negative file size input
+                }
+                Err(error) => {
+                    eprintln!("Invalid file size: {}. Size cannot be a non-numeric value.", error);
+                    std::process::exit(4); // Exit with code 4 for non-numeric file size input
+                }
+            };
 
-    let input = format!("{} {}", args[1], args[2]); // Combine the file size and unit input for display
+            // Validate the unit argument
+            let unit = match positional[1].to_lowercase().as_str() {
+                "bytes" | "kb" | "mb" | "gb" | "kib" | "mib" | "gib" => positional[1].to_lowercase(),
+                _ => {
+                    eprintln!(
+                        "Invalid unit: '{}'. Supported units: 'bytes', 'kb', 'mb', 'gb', 'kib', 'mib', or 'gib'.",
+                        positional[1]
+                    );
+                    std::process::exit(2); // Exit with code 2 for invalid unit input
+                }
+            };
 
-    // Validate and parse the file size argument
-    let file_size: f64 = match args[1].parse::<f64>() {
-        Ok(size_input) if size_input >= 0.0 => size_input,
-        Ok(_) => {
-            eprintln!("Invalid file size. Size cannot be a negative number.");
-            std::process::exit(4); // Exit with code 4 for negative file size input
+            match FileSize::new(file_size, &unit) {
+                Ok(magnitude) => {
+                    let adjustment = SizeAdjustment { operator, magnitude };
+                    let reference = resolve_reference(adjustment.operator, reference_bytes);
+                    print_conversion(&input, adjustment.apply(reference), base, humanize);
+                }
+                Err(error) => exit_on_file_size_error(&error),
+            }
         }
-        Err(error) => {
-            eprintln!("Invalid file size: {}. Size cannot be a non-numeric value.", error);
-            std::process::exit(4); // Exit with code 4 for non-numeric file size input
+        // Single combined token (`1.5GiB`, `+500mb`, `%4096`), or `-` to read one token per line from stdin.
+        1 if positional[0] == "-" => {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                let line = line.unwrap_or_else(|error| {
+                    eprintln!("Error reading from stdin: {}", error);
+                    std::process::exit(1);
+                });
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                match SizeAdjustment::parse(line) {
+                    Ok(adjustment) => {
+                        let reference = resolve_reference(adjustment.operator, reference_bytes);
+                        print_conversion(line, adjustment.apply(reference), base, humanize)
+                    }
+                    Err(error) => eprintln!("{}", error),
+                }
+            }
         }
-    };
-
-    // Validate the unit argument
-    let unit = match args[2].to_lowercase().as_str() {
-        "bytes" | "kb" | "mb" | "gb" => args[2].to_lowercase(),
+        1 => match SizeAdjustment::parse(&positional[0]) {
+            Ok(adjustment) => {
+                let reference = resolve_reference(adjustment.operator, reference_bytes);
+                print_conversion(&positional[0], adjustment.apply(reference), base, humanize)
+            }
+            Err(error) => exit_on_file_size_error(&error),
+        },
         _ => {
             eprintln!(
-                "Invalid unit: '{}'. Supported units: 'bytes', 'kb', 'mb', or 'gb'.",
-                args[2]
+                "Usage: {} <file_size> <unit (bytes/kb/mb/gb/kib/mib/gib)> | <combined_size (e.g. 1.5GiB, +500mb, %4096)> | - [--base decimal|binary|conventional] [--humanize] [--reference <size>] | --filter <+/-threshold>",
+                args[0]
             );
-            std::process::exit(2); // Exit with code 2 for invalid unit input
-        }
-    };
-
-    // Create a FileSize instance and normalize it to bytes. Then, convert it to Sizes and display the results.
-    match FileSize::new(file_size, &unit) {
-        Ok(file_size) => {
-            let size_in_bytes = file_size.normalize_to_bytes(); // Normalize the file size to bytes
-            let sizes = Sizes::from_bytes(size_in_bytes); // Create a Sizes instance to hold the file size conversions
-            println!("file size ({}):", input); // Display the file size and unit input
-            println!("{}", sizes); // Display the file size in different units
-            std::process::exit(0); // Exit with code 0 for successful execution
+            std::process::exit(2); // Exit with code 2 for incorrect command usage
         }
-        Err(error) => {
-            eprintln!("{}", error); // Display appropriate error message if the file size or unit are invalid
-
-            // Exit with appropriate code based on the error
-            if error.contains("Invalid file size. Size cannot be a negative number.") {
-                std::process::exit(4); // Exit with code 4 for invalid file size
-            } else {
-                std::process::exit(2); // Exit with code 2 for invalid unit
+    }
+
+    std::process::exit(0); // Exit with code 0 for successful execution
+}
+
+// Resolve the reference size an adjustment applies against, erroring out
+// instead of silently defaulting to zero when the operator needs a
+// `--reference` that wasn't supplied.
+fn resolve_reference(operator: Operator, reference_bytes: Option<u64>) -> u64 {
+    match reference_bytes {
+        Some(bytes) => bytes,
+        None => match operator {
+            Operator::Subtract | Operator::RoundUpTo | Operator::RoundDownTo => {
+                eprintln!("Error: this adjustment requires --reference <size> to be set.");
+                std::process::exit(2);
             }
-        }
+            Operator::Set | Operator::Add => 0,
+        },
+    }
+}
+
+// Print either the full conversion block or the humanized single-unit form
+// for an already-normalized byte count, labeled with the original input token.
+fn print_conversion(input: &str, size_in_bytes: u64, base: Base, humanize: bool) {
+    if humanize {
+        println!("{}", base.humanize(size_in_bytes, 2)); // Print the single best-fit unit
+    } else {
+        let sizes = Sizes::from_bytes(size_in_bytes, base); // Create a Sizes instance to hold the file size conversions
+        println!("file size ({}):", input); // Display the file size and unit input
+        println!("{}", sizes); // Display the file size in different units
+    }
+}
+
+// Display a file-size parse error and exit with the appropriate code.
+fn exit_on_file_size_error(error: &str) -> ! {
+    eprintln!("{}", error); // Display appropriate error message if the file size or unit are invalid
+
+    // Exit with appropriate code based on the error
+    if error.starts_with("Invalid file size") {
+        std::process::exit(4); // Exit with code 4 for invalid file size
+    } else {
+        std::process::exit(2); // Exit with code 2 for invalid unit
     }
 }